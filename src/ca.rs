@@ -0,0 +1,126 @@
+//! Content-addressing metadata carried by a narinfo's `CA:` line, for store
+//! paths produced by fixed-output derivations or `builtins.fetch*`.
+
+use crate::hash::{Hash, HashParseErr};
+
+/// Whether a fixed-output hash was computed over the NAR serialization
+/// (`Recursive`) or over the raw file contents (`Flat`).
+#[derive(PartialEq, Eq, Debug)]
+pub enum CAMethod {
+    Flat,
+    Recursive,
+}
+
+/// A parsed `CA:` value, e.g. `fixed:r:sha256:1abc...` or `text:sha256:0ccq...`.
+#[derive(PartialEq, Eq, Debug)]
+pub enum CAHash {
+    /// `text:<algo>:<digest>`, used for store paths derived from known text
+    /// (e.g. the output of `builtins.toFile`).
+    Text(Hash),
+    /// `fixed:[r:]<algo>:<digest>`, used for fixed-output derivations and
+    /// flat/recursive file hashing.
+    Fixed { method: CAMethod, hash: Hash },
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum CAHashParseErr<'a> {
+    UnknownScheme(&'a str),
+    InvalidHash(&'a str, HashParseErr<'a>),
+}
+
+impl CAHash {
+    pub fn parse(s: &str) -> Result<CAHash, CAHashParseErr<'_>> {
+        if let Some(rest) = s.strip_prefix("text:") {
+            let hash = Hash::parse(rest).map_err(|e| CAHashParseErr::InvalidHash(rest, e))?;
+            return Ok(CAHash::Text(hash));
+        }
+
+        if let Some(rest) = s.strip_prefix("fixed:") {
+            let (method, algo_digest) = match rest.strip_prefix("r:") {
+                Some(algo_digest) => (CAMethod::Recursive, algo_digest),
+                None => (CAMethod::Flat, rest),
+            };
+
+            let hash = Hash::parse(algo_digest)
+                .map_err(|e| CAHashParseErr::InvalidHash(algo_digest, e))?;
+
+            return Ok(CAHash::Fixed { method, hash });
+        }
+
+        Err(CAHashParseErr::UnknownScheme(s))
+    }
+}
+
+impl std::fmt::Display for CAHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CAHash::Text(hash) => write!(f, "text:{hash}"),
+            CAHash::Fixed {
+                method: CAMethod::Recursive,
+                hash,
+            } => write!(f, "fixed:r:{hash}"),
+            CAHash::Fixed {
+                method: CAMethod::Flat,
+                hash,
+            } => write!(f, "fixed:{hash}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_unknown_scheme() {
+        assert_eq!(
+            CAHash::parse("bogus:sha256:abc"),
+            Err(CAHashParseErr::UnknownScheme("bogus:sha256:abc"))
+        );
+    }
+
+    #[test]
+    fn parse_text() {
+        let ca = CAHash::parse("text:sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i")
+            .expect("should parse");
+        assert!(matches!(ca, CAHash::Text(_)));
+    }
+
+    #[test]
+    fn parse_fixed_flat() {
+        let ca = CAHash::parse("fixed:sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i")
+            .expect("should parse");
+        assert!(matches!(
+            ca,
+            CAHash::Fixed {
+                method: CAMethod::Flat,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_fixed_recursive() {
+        let ca =
+            CAHash::parse("fixed:r:sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i")
+                .expect("should parse");
+        assert!(matches!(
+            ca,
+            CAHash::Fixed {
+                method: CAMethod::Recursive,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn display_roundtrips() {
+        for input in [
+            "text:sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i",
+            "fixed:sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i",
+            "fixed:r:sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i",
+        ] {
+            assert_eq!(CAHash::parse(input).unwrap().to_string(), input);
+        }
+    }
+}
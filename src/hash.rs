@@ -0,0 +1,129 @@
+//! A typed, encoding-aware representation of the `algo:digest` hash fields
+//! found throughout narinfo files (`FileHash`, `NarHash`, ...).
+
+use data_encoding::HEXLOWER;
+
+use crate::nixbase32;
+
+/// The textual encoding a hash's digest was found in. Nix caches emit
+/// either, so we record which one was seen to reproduce it on output.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum HashEncoding {
+    NixBase32,
+    Hex,
+}
+
+/// A parsed `algo:digest` hash, e.g. `sha256:0ccqg4il...`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Hash {
+    pub algo: String,
+    digest: Vec<u8>,
+    encoding: HashEncoding,
+}
+
+/// The hash algorithms Nix actually emits in narinfo `algo:digest` fields.
+const KNOWN_ALGOS: &[&str] = &["md5", "sha1", "sha256", "sha512"];
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum HashParseErr<'a> {
+    MissingColon(&'a str),
+    UnknownAlgo(&'a str),
+    InvalidDigest(&'a str),
+}
+
+impl Hash {
+    pub fn parse(s: &str) -> Result<Hash, HashParseErr<'_>> {
+        let (algo, digest_str) = s.split_once(':').ok_or(HashParseErr::MissingColon(s))?;
+
+        if !KNOWN_ALGOS.contains(&algo) {
+            return Err(HashParseErr::UnknownAlgo(algo));
+        }
+
+        // A SHA-256 hex digest is 64 characters; nixbase32 encodes the same
+        // 32 bytes in 52. Length disambiguates the encoding.
+        let (digest, encoding) = if digest_str.len() == 64 {
+            (
+                HEXLOWER
+                    .decode(digest_str.as_bytes())
+                    .map_err(|_| HashParseErr::InvalidDigest(s))?,
+                HashEncoding::Hex,
+            )
+        } else {
+            (
+                nixbase32::decode(digest_str).map_err(|_| HashParseErr::InvalidDigest(s))?,
+                HashEncoding::NixBase32,
+            )
+        };
+
+        Ok(Hash {
+            algo: algo.to_string(),
+            digest,
+            encoding,
+        })
+    }
+
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    pub fn encoding(&self) -> HashEncoding {
+        self.encoding
+    }
+}
+
+impl std::fmt::Display for Hash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digest = match self.encoding {
+            HashEncoding::NixBase32 => nixbase32::encode(&self.digest),
+            HashEncoding::Hex => HEXLOWER.encode(&self.digest),
+        };
+
+        write!(f, "{}:{}", self.algo, digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_missing_colon() {
+        assert_eq!(Hash::parse("nocolonhere"), Err(HashParseErr::MissingColon("nocolonhere")));
+    }
+
+    #[test]
+    fn parse_unknown_algo() {
+        assert_eq!(
+            Hash::parse("notarealalgo:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i"),
+            Err(HashParseErr::UnknownAlgo("notarealalgo"))
+        );
+    }
+
+    #[test]
+    fn parse_nixbase32() {
+        let hash = Hash::parse("sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i").unwrap();
+        assert_eq!(hash.algo, "sha256");
+        assert_eq!(hash.encoding(), HashEncoding::NixBase32);
+        assert_eq!(hash.digest().len(), 32);
+    }
+
+    #[test]
+    fn parse_hex() {
+        let hex_digest = "a".repeat(64);
+        let hash = Hash::parse(&format!("sha256:{}", hex_digest)).unwrap();
+        assert_eq!(hash.encoding(), HashEncoding::Hex);
+        assert_eq!(hash.digest().len(), 32);
+    }
+
+    #[test]
+    fn display_roundtrips_nixbase32() {
+        let input = "sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i";
+        assert_eq!(Hash::parse(input).unwrap().to_string(), input);
+    }
+
+    #[test]
+    fn display_roundtrips_hex() {
+        let input = format!("sha256:{}", "ab".repeat(32));
+        assert_eq!(Hash::parse(&input).unwrap().to_string(), input);
+    }
+}
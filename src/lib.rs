@@ -1,14 +1,120 @@
 use std::path::PathBuf;
 
-/// The hash-name part of a store path
+mod ca;
+mod hash;
+mod nix_cache_info;
+mod nixbase32;
+mod pubkey;
+mod store_path;
+pub use ca::{CAHash, CAHashParseErr, CAMethod};
+pub use hash::{Hash, HashEncoding, HashParseErr};
+pub use nix_cache_info::{NixCacheInfo, ParseErr as NixCacheInfoParseErr};
+pub use pubkey::{ParsedSignature, PubKey, PubKeyParseErr, SignatureParseErr};
+pub use store_path::StorePathParseErr;
+
+use store_path::DIGEST_LEN;
+
+/// The hash-name part of a store path, structurally validated: a 32-character
+/// nixbase32 digest (which decodes to 20 bytes) followed by `-` and a name.
 /// ie: xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23
-#[derive(PartialEq, Eq, Debug)]
-pub struct NarInfoId(String);
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct NarInfoId {
+    raw: String,
+    digest: Vec<u8>,
+}
+
+impl NarInfoId {
+    pub fn parse(s: &str) -> Result<NarInfoId, StorePathParseErr<'_>> {
+        let (digest, _name) = store_path::parse_component(s)?;
+        Ok(NarInfoId {
+            raw: s.to_string(),
+            digest,
+        })
+    }
+
+    fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
 
-/// The hash-name part of a derivation's store path.
+    pub fn name(&self) -> &str {
+        &self.raw[DIGEST_LEN + 1..]
+    }
+}
+
+/// The hash-name part of a derivation's store path, with the same digest/name
+/// validation as [`NarInfoId`] plus a mandatory `.drv` suffix.
 /// ie: a6xizp18g0sch9z7493p3irq632kzlym-bash-interactive-4.4-p23.drv
 #[derive(PartialEq, Eq, Debug)]
-pub struct DerivationId(String);
+pub struct DerivationId {
+    raw: String,
+    digest: Vec<u8>,
+}
+
+impl DerivationId {
+    pub fn parse(s: &str) -> Result<DerivationId, StorePathParseErr<'_>> {
+        let without_drv = s
+            .strip_suffix(".drv")
+            .ok_or(StorePathParseErr::MissingDrvSuffix(s))?;
+        let (digest, _name) = store_path::parse_component(without_drv)?;
+        Ok(DerivationId {
+            raw: s.to_string(),
+            digest,
+        })
+    }
+
+    fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    pub fn name(&self) -> &str {
+        let without_drv = &self.raw[..self.raw.len() - ".drv".len()];
+        &without_drv[DIGEST_LEN + 1..]
+    }
+}
+
+/// A single `Sig:` line's raw text, e.g.
+/// `cache.nixos.org-1:bm90YXJlYWxzaWduYXR1cmU=`. Binary caches may attach
+/// zero or more of these.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Signature(String);
+
+impl Signature {
+    fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Quirks observed while parsing a narinfo that a byte-identical
+/// `to_string()` round-trip needs to know about. These aren't malformed
+/// files; they're spellings that `cache.nixos.org` itself produces, and
+/// silently "fixing" them on output would change the fingerprint a
+/// signature was computed over.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub struct ParseQuirks {
+    /// One or more lines used a key this parser doesn't recognize. Those
+    /// lines are not kept, so `to_string()` does not reproduce them.
+    pub had_unknown_keys: bool,
+    /// The `Compression` line was absent, and `bzip2` was assumed.
+    pub compression_defaulted: bool,
+    /// `NarHash` was given in hex rather than nixbase32.
+    pub nar_hash_was_hex: bool,
+    /// `References` arrived in a different order than sorted.
+    pub references_out_of_order: bool,
+}
 
 /// A parsed NarInfo file, which can be fetched from
 /// https://cache.nixos.org/storepathhash.narinfo.
@@ -28,13 +134,13 @@ pub struct NarInfo {
     pub compression: String,
 
     /// The hash of the compressed NAR
-    pub file_hash: String,
+    pub file_hash: Hash,
 
     /// The size of the compressed NAR
     pub file_size: u64,
 
     /// The hash of the decompressed NAR
-    pub nar_hash: String,
+    pub nar_hash: Hash,
 
     /// The size of the decompressed NAR
     pub nar_size: u64,
@@ -42,36 +148,63 @@ pub struct NarInfo {
     /// Other NARs this NAR's store path depends on
     pub references: Vec<NarInfoId>,
 
-    /// The name of the Derivation used to build this store path
-    pub deriver: DerivationId,
+    /// The name of the Derivation used to build this store path, if known.
+    /// Some caches omit this.
+    pub deriver: Option<DerivationId>,
+
+    /// The Nix system double (e.g. `x86_64-linux`) this store path was built
+    /// for, if the cache advertised one.
+    pub system: Option<String>,
+
+    /// Zero or more signatures, each over the narinfo's fingerprint
+    /// (`1;{storepath};{nar_hash};{nar_size};{refs}`, see [`NarInfo::verify`]).
+    pub signatures: Vec<Signature>,
+
+    /// Content-addressing metadata, present for fixed-output derivations,
+    /// sources, and other content-addressed store paths.
+    pub ca: Option<CAHash>,
 
-    /// The signature which is against the contents of the narinfo minus the signature line.
-    pub signature: String,
+    /// Non-canonical spellings observed while parsing, needed to reproduce
+    /// the original bytes on serialization.
+    pub quirks: ParseQuirks,
 }
 
 #[derive(PartialEq, Eq, Debug)]
 enum NarInfoDatum<'a> {
+    CA(CAHash),
     Compression(&'a str),
     Deriver(DerivationId),
-    FileHash(&'a str),
+    FileHash(Hash),
     FileSize(u64),
-    NarHash(&'a str),
+    NarHash(Hash),
     NarSize(u64),
     References(Vec<NarInfoId>),
     Sig(&'a str),
     StorePath(PathBuf),
+    System(&'a str),
     Url(&'a str),
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseErr<'a> {
+pub enum ParseErr<'a> {
     LineCorruptNoColon(&'a str),
     LineUnknownKey(&'a str),
     InvalidU64(&'a str, std::num::ParseIntError),
     UnexpectedSpace(&'a str, usize),
+    InvalidHash(&'a str),
+    InvalidCA(&'a str),
+    InvalidStorePathId(StorePathParseErr<'a>),
+    /// A `StorePath:` value wasn't rooted at `/nix/store/`.
+    InvalidStorePath(&'a str),
 }
 
-type ParseResult = Result<NarInfo, ()>;
+#[derive(PartialEq, Eq, Debug)]
+pub enum ParseStringErr<'a> {
+    Line(ParseErr<'a>),
+    MissingField(&'static str),
+}
+
+type ParseResult<'a> = Result<NarInfo, ParseStringErr<'a>>;
 
 impl NarInfo {
     fn parse_str_no_spaces<'a>(key: &'a str, remainder: &'a str) -> Result<&'a str, ParseErr<'a>> {
@@ -88,7 +221,7 @@ impl NarInfo {
             .map_err(|e| ParseErr::InvalidU64(key, e))
     }
 
-    fn parse_line(line: &str) -> Result<NarInfoDatum, ParseErr> {
+    fn parse_line(line: &str) -> Result<NarInfoDatum<'_>, ParseErr<'_>> {
         let (key, remainder): (&str, &str) = line
             .split_once(":")
             .ok_or(ParseErr::LineCorruptNoColon(line))?;
@@ -96,17 +229,214 @@ impl NarInfo {
         let remainder = remainder.trim();
 
         match key {
+            "CA" => Ok(NarInfoDatum::CA(
+                CAHash::parse(remainder).map_err(|_| ParseErr::InvalidCA(remainder))?,
+            )),
             "Compression" => Ok(NarInfoDatum::Compression(Self::parse_str_no_spaces(
                 key, remainder,
             )?)),
+            "Deriver" => Ok(NarInfoDatum::Deriver(
+                DerivationId::parse(remainder).map_err(ParseErr::InvalidStorePathId)?,
+            )),
+            "FileHash" => Ok(NarInfoDatum::FileHash(
+                Hash::parse(remainder).map_err(|_| ParseErr::InvalidHash(remainder))?,
+            )),
             "FileSize" => Ok(NarInfoDatum::FileSize(Self::parse_u64(key, remainder)?)),
+            "NarHash" => Ok(NarInfoDatum::NarHash(
+                Hash::parse(remainder).map_err(|_| ParseErr::InvalidHash(remainder))?,
+            )),
             "NarSize" => Ok(NarInfoDatum::NarSize(Self::parse_u64(key, remainder)?)),
+            "References" => Ok(NarInfoDatum::References(
+                remainder
+                    .split_whitespace()
+                    .map(NarInfoId::parse)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(ParseErr::InvalidStorePathId)?,
+            )),
+            "Sig" => Ok(NarInfoDatum::Sig(remainder)),
+            "StorePath" => {
+                let component = remainder
+                    .strip_prefix("/nix/store/")
+                    .ok_or(ParseErr::InvalidStorePath(remainder))?;
+                store_path::parse_component(component).map_err(ParseErr::InvalidStorePathId)?;
+                Ok(NarInfoDatum::StorePath(PathBuf::from(remainder)))
+            }
+            "System" => Ok(NarInfoDatum::System(remainder)),
+            "URL" => Ok(NarInfoDatum::Url(remainder)),
             unknown_key => Err(ParseErr::LineUnknownKey(unknown_key)),
         }
     }
 
-    pub fn parse_string(nar: String) -> ParseResult {
-        todo!();
+    pub fn parse_string(nar: &str) -> ParseResult<'_> {
+        let mut storepath = None;
+        let mut url = None;
+        let mut compression = None;
+        let mut file_hash = None;
+        let mut file_size = None;
+        let mut nar_hash = None;
+        let mut nar_size = None;
+        let mut references = None;
+        let mut deriver = None;
+        let mut system = None;
+        let mut signatures = Vec::new();
+        let mut ca = None;
+        let mut quirks = ParseQuirks::default();
+
+        for line in nar.lines() {
+            if line.is_empty() {
+                continue;
+            }
+
+            match Self::parse_line(line) {
+                Ok(NarInfoDatum::CA(v)) => ca = Some(v),
+                Ok(NarInfoDatum::StorePath(v)) => storepath = Some(v),
+                Ok(NarInfoDatum::Url(v)) => url = Some(v.to_string()),
+                Ok(NarInfoDatum::Compression(v)) => compression = Some(v.to_string()),
+                Ok(NarInfoDatum::FileHash(v)) => file_hash = Some(v),
+                Ok(NarInfoDatum::FileSize(v)) => file_size = Some(v),
+                Ok(NarInfoDatum::NarHash(v)) => nar_hash = Some(v),
+                Ok(NarInfoDatum::NarSize(v)) => nar_size = Some(v),
+                Ok(NarInfoDatum::References(v)) => references = Some(v),
+                Ok(NarInfoDatum::Deriver(v)) => deriver = Some(v),
+                Ok(NarInfoDatum::System(v)) => system = Some(v.to_string()),
+                Ok(NarInfoDatum::Sig(v)) => signatures.push(Signature(v.to_string())),
+                Err(ParseErr::LineUnknownKey(_)) => quirks.had_unknown_keys = true,
+                Err(e) => return Err(ParseStringErr::Line(e)),
+            }
+        }
+
+        // Required fields are checked in declaration order, so the first
+        // `MissingField` a caller sees always names the first gap in the
+        // source text, not whichever field happens to be built last.
+        let storepath = storepath.ok_or(ParseStringErr::MissingField("StorePath"))?;
+        let url = url.ok_or(ParseStringErr::MissingField("URL"))?;
+
+        let compression = match compression {
+            Some(c) => c,
+            None => {
+                quirks.compression_defaulted = true;
+                "bzip2".to_string()
+            }
+        };
+
+        let file_hash = file_hash.ok_or(ParseStringErr::MissingField("FileHash"))?;
+        let file_size = file_size.ok_or(ParseStringErr::MissingField("FileSize"))?;
+
+        let nar_hash = nar_hash.ok_or(ParseStringErr::MissingField("NarHash"))?;
+        quirks.nar_hash_was_hex = nar_hash.encoding() == HashEncoding::Hex;
+
+        let nar_size = nar_size.ok_or(ParseStringErr::MissingField("NarSize"))?;
+
+        let references = references.unwrap_or_default();
+        quirks.references_out_of_order = {
+            let mut sorted = references.iter().collect::<Vec<_>>();
+            sorted.sort();
+            sorted != references.iter().collect::<Vec<_>>()
+        };
+
+        Ok(NarInfo {
+            storepath,
+            url,
+            compression,
+            file_hash,
+            file_size,
+            nar_hash,
+            nar_size,
+            references,
+            deriver,
+            system,
+            signatures,
+            ca,
+            quirks,
+        })
+    }
+
+    /// Reconstructs the string that narinfo signatures are computed over:
+    /// `1;{storepath};{nar_hash};{nar_size};{refs}`, where `{refs}` is the
+    /// comma-joined list of full `/nix/store/...` reference paths in the
+    /// order they appeared.
+    fn fingerprint(&self) -> String {
+        let refs = self
+            .references
+            .iter()
+            .map(|r| format!("/nix/store/{}", r.as_str()))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "1;{};{};{};{}",
+            self.storepath.display(),
+            self.nar_hash,
+            self.nar_size,
+            refs
+        )
+    }
+
+    /// Checks the narinfo's signatures against the given trusted public
+    /// keys, returning `true` if any signature's name matches a key and its
+    /// ed25519 signature validates over this narinfo's fingerprint.
+    pub fn verify(&self, keys: &[PubKey]) -> bool {
+        let fingerprint = self.fingerprint();
+
+        self.signatures.iter().any(|signature| {
+            let Ok(sig) = ParsedSignature::parse(signature.as_str()) else {
+                return false;
+            };
+
+            keys.iter().any(|key| sig.verify(&fingerprint, key))
+        })
+    }
+}
+
+impl std::fmt::Display for NarInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "StorePath: {}", self.storepath.display())?;
+        writeln!(f, "URL: {}", self.url)?;
+
+        if !self.quirks.compression_defaulted {
+            writeln!(f, "Compression: {}", self.compression)?;
+        }
+
+        writeln!(f, "FileHash: {}", self.file_hash)?;
+        writeln!(f, "FileSize: {}", self.file_size)?;
+        writeln!(f, "NarHash: {}", self.nar_hash)?;
+        writeln!(f, "NarSize: {}", self.nar_size)?;
+        writeln!(
+            f,
+            "References: {}",
+            self.references
+                .iter()
+                .map(NarInfoId::as_str)
+                .collect::<Vec<_>>()
+                .join(" ")
+        )?;
+
+        if let Some(deriver) = &self.deriver {
+            writeln!(f, "Deriver: {}", deriver.as_str())?;
+        }
+
+        if let Some(system) = &self.system {
+            writeln!(f, "System: {}", system)?;
+        }
+
+        write!(
+            f,
+            "{}",
+            self.signatures
+                .iter()
+                .map(|sig| format!("Sig: {sig}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )?;
+
+        if let Some(ca) = &self.ca {
+            if !self.signatures.is_empty() {
+                writeln!(f)?;
+            }
+            write!(f, "CA: {ca}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -114,6 +444,88 @@ impl NarInfo {
 mod tests {
     use super::*;
 
+    fn example_narinfo() -> NarInfo {
+        NarInfo {
+            storepath: PathBuf::from(
+                "/nix/store/xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23",
+            ),
+            url: "nar/abc.nar.xz".to_string(),
+            compression: "xz".to_string(),
+            file_hash: Hash::parse("sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i")
+                .unwrap(),
+            file_size: 123,
+            nar_hash: Hash::parse("sha256:1h7ymc5i5rwfb5rbdyqvfsd0yzsp0rivxcnxn1yh3bp7p963x4nz")
+                .unwrap(),
+            nar_size: 456,
+            references: vec![],
+            deriver: Some(
+                DerivationId::parse("a6xizp18g0sch9z7493p3irq632kzlym-bash-interactive-4.4-p23.drv")
+                    .unwrap(),
+            ),
+            system: None,
+            signatures: vec![Signature(
+                "cache.nixos.org-1:bm90YXJlYWxzaWduYXR1cmU=".to_string(),
+            )],
+            ca: None,
+            quirks: ParseQuirks::default(),
+        }
+    }
+
+    #[test]
+    fn fingerprint_empty_references() {
+        let narinfo = example_narinfo();
+        assert_eq!(
+            narinfo.fingerprint(),
+            "1;/nix/store/xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23;sha256:1h7ymc5i5rwfb5rbdyqvfsd0yzsp0rivxcnxn1yh3bp7p963x4nz;456;"
+        );
+    }
+
+    #[test]
+    fn fingerprint_with_references() {
+        let mut narinfo = example_narinfo();
+        narinfo.references = vec![
+            NarInfoId::parse("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa-foo").unwrap(),
+            NarInfoId::parse("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb-bar").unwrap(),
+        ];
+        assert_eq!(
+            narinfo.fingerprint(),
+            "1;/nix/store/xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23;sha256:1h7ymc5i5rwfb5rbdyqvfsd0yzsp0rivxcnxn1yh3bp7p963x4nz;456;/nix/store/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa-foo,/nix/store/bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb-bar"
+        );
+    }
+
+    #[test]
+    fn verify_rejects_unmatched_key() {
+        let narinfo = example_narinfo();
+        let keys = vec![PubKey::parse(
+            "other-key-1:6NCHdD59X431o0gWypbMrAURkbJ16ZPMQFGspcDShjY=",
+        )
+        .unwrap()];
+        assert!(!narinfo.verify(&keys));
+    }
+
+    #[test]
+    fn verify_accepts_real_signature() {
+        use data_encoding::BASE64;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let mut narinfo = example_narinfo();
+
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let signature = signing_key.sign(narinfo.fingerprint().as_bytes());
+        narinfo.signatures = vec![Signature(format!(
+            "test-key-1:{}",
+            BASE64.encode(&signature.to_bytes())
+        ))];
+
+        let keys = vec![PubKey::parse(&format!(
+            "test-key-1:{}",
+            BASE64.encode(signing_key.verifying_key().as_bytes())
+        ))
+        .unwrap()];
+
+        assert!(narinfo.verify(&keys));
+    }
+
     #[test]
     fn parse_line_badly_formatted() {
         assert_eq!(
@@ -183,4 +595,136 @@ mod tests {
             Ok(NarInfoDatum::Compression("xz"))
         );
     }
+
+    const EXAMPLE_NARINFO: &str = "StorePath: /nix/store/xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23\n\
+URL: nar/0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i.nar.xz\n\
+Compression: xz\n\
+FileHash: sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i\n\
+FileSize: 1234567\n\
+NarHash: sha256:1h7ymc5i5rwfb5rbdyqvfsd0yzsp0rivxcnxn1yh3bp7p963x4nz\n\
+NarSize: 7654321\n\
+References: \n\
+Deriver: a6xizp18g0sch9z7493p3irq632kzlym-bash-interactive-4.4-p23.drv\n\
+Sig: cache.nixos.org-1:bm90YXJlYWxzaWduYXR1cmU=";
+
+    #[test]
+    fn parse_string_roundtrips_byte_identical() {
+        let narinfo = NarInfo::parse_string(EXAMPLE_NARINFO).expect("should parse");
+        assert_eq!(narinfo.to_string(), EXAMPLE_NARINFO);
+    }
+
+    #[test]
+    fn parse_string_no_quirks_for_canonical_file() {
+        let narinfo = NarInfo::parse_string(EXAMPLE_NARINFO).expect("should parse");
+        assert_eq!(narinfo.quirks, ParseQuirks::default());
+    }
+
+    #[test]
+    fn parse_string_missing_field() {
+        let err = NarInfo::parse_string(
+            "StorePath: /nix/store/xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23",
+        );
+        assert!(matches!(err, Err(ParseStringErr::MissingField("URL"))));
+    }
+
+    #[test]
+    fn parse_line_rejects_malformed_store_path() {
+        assert!(matches!(
+            NarInfo::parse_line("StorePath: /nix/store/not-a-valid-digest"),
+            Err(ParseErr::InvalidStorePathId(_))
+        ));
+        assert!(matches!(
+            NarInfo::parse_line("StorePath: /not/nix/store/xmxgxig6zxrixicc7905ssgb4yc3lysa-bash"),
+            Err(ParseErr::InvalidStorePath(_))
+        ));
+    }
+
+    #[test]
+    fn parse_string_defaults_missing_compression() {
+        let without_compression = EXAMPLE_NARINFO.replace("Compression: xz\n", "");
+        let narinfo = NarInfo::parse_string(&without_compression).expect("should parse");
+        assert!(narinfo.quirks.compression_defaulted);
+        assert_eq!(narinfo.compression, "bzip2");
+        // Serializing back shouldn't invent a Compression line that wasn't there.
+        assert_eq!(narinfo.to_string(), without_compression);
+    }
+
+    #[test]
+    fn parse_string_tolerates_unknown_keys() {
+        let with_unknown = format!("SomeFutureField: whatever\n{}", EXAMPLE_NARINFO);
+        let narinfo = NarInfo::parse_string(&with_unknown).expect("should parse");
+        assert!(narinfo.quirks.had_unknown_keys);
+    }
+
+    #[test]
+    fn parse_string_detects_hex_narhash() {
+        let hex_narhash = format!("sha256:{}", "ab".repeat(32));
+        let with_hex = EXAMPLE_NARINFO.replace(
+            "NarHash: sha256:1h7ymc5i5rwfb5rbdyqvfsd0yzsp0rivxcnxn1yh3bp7p963x4nz",
+            &format!("NarHash: {}", hex_narhash),
+        );
+        let narinfo = NarInfo::parse_string(&with_hex).expect("should parse");
+        assert!(narinfo.quirks.nar_hash_was_hex);
+        assert_eq!(narinfo.to_string(), with_hex);
+    }
+
+    #[test]
+    fn parse_string_detects_out_of_order_references() {
+        let with_refs = EXAMPLE_NARINFO.replace(
+            "References: ",
+            "References: bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb-bar aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa-foo",
+        );
+        let narinfo = NarInfo::parse_string(&with_refs).expect("should parse");
+        assert!(narinfo.quirks.references_out_of_order);
+        // Original (non-canonical) order is preserved on re-serialization.
+        assert_eq!(narinfo.to_string(), with_refs);
+    }
+
+    #[test]
+    fn parse_string_accumulates_multiple_signatures() {
+        let with_extra_sig = format!(
+            "{}\nSig: other-key-1:aW52YWxpZHNpZ25hdHVyZWhlcmUh",
+            EXAMPLE_NARINFO
+        );
+        let narinfo = NarInfo::parse_string(&with_extra_sig).expect("should parse");
+        assert_eq!(narinfo.signatures.len(), 2);
+        assert_eq!(narinfo.to_string(), with_extra_sig);
+    }
+
+    #[test]
+    fn parse_string_tolerates_missing_deriver() {
+        let without_deriver =
+            EXAMPLE_NARINFO.replace("Deriver: a6xizp18g0sch9z7493p3irq632kzlym-bash-interactive-4.4-p23.drv\n", "");
+        let narinfo = NarInfo::parse_string(&without_deriver).expect("should parse");
+        assert!(narinfo.deriver.is_none());
+        assert_eq!(narinfo.to_string(), without_deriver);
+    }
+
+    #[test]
+    fn parse_string_accepts_system() {
+        let with_system = EXAMPLE_NARINFO.replace(
+            "Deriver: a6xizp18g0sch9z7493p3irq632kzlym-bash-interactive-4.4-p23.drv\n",
+            "Deriver: a6xizp18g0sch9z7493p3irq632kzlym-bash-interactive-4.4-p23.drv\nSystem: x86_64-linux\n",
+        );
+        let narinfo = NarInfo::parse_string(&with_system).expect("should parse");
+        assert_eq!(narinfo.system.as_deref(), Some("x86_64-linux"));
+        assert_eq!(narinfo.to_string(), with_system);
+    }
+
+    #[test]
+    fn parse_string_accepts_ca() {
+        let with_ca = format!(
+            "{}\nCA: fixed:r:sha256:0ccqg4il9d7jjgcm3p9c6a0xkbpfg4rh9qk6aaxzhh60qnrlr67i",
+            EXAMPLE_NARINFO
+        );
+        let narinfo = NarInfo::parse_string(&with_ca).expect("should parse");
+        assert!(matches!(
+            narinfo.ca,
+            Some(CAHash::Fixed {
+                method: CAMethod::Recursive,
+                ..
+            })
+        ));
+        assert_eq!(narinfo.to_string(), with_ca);
+    }
 }
@@ -0,0 +1,155 @@
+//! Parser for the `nix-cache-info` file a binary cache serves alongside its
+//! narinfos, e.g. `https://cache.nixos.org/nix-cache-info`.
+
+use std::path::PathBuf;
+
+/// A parsed `nix-cache-info` file.
+#[derive(PartialEq, Eq, Debug)]
+pub struct NixCacheInfo {
+    /// The store directory this cache's paths live under. Almost always
+    /// `/nix/store`.
+    pub store_dir: PathBuf,
+
+    /// Whether tools should batch-query this cache for path availability
+    /// rather than probing one path at a time.
+    pub want_mass_query: bool,
+
+    /// This cache's priority among multiple configured substituters; lower
+    /// numbers are preferred.
+    pub priority: i32,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum NixCacheInfoDatum<'a> {
+    StoreDir(&'a str),
+    WantMassQuery(bool),
+    Priority(i32),
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ParseErr<'a> {
+    LineCorruptNoColon(&'a str),
+    LineUnknownKey(&'a str),
+    InvalidBool(&'a str),
+    InvalidInt(&'a str, std::num::ParseIntError),
+}
+
+impl NixCacheInfo {
+    fn parse_line(line: &str) -> Result<NixCacheInfoDatum<'_>, ParseErr<'_>> {
+        let (key, remainder) = line
+            .split_once(':')
+            .ok_or(ParseErr::LineCorruptNoColon(line))?;
+
+        let remainder = remainder.trim();
+
+        match key {
+            "StoreDir" => Ok(NixCacheInfoDatum::StoreDir(remainder)),
+            "WantMassQuery" => match remainder {
+                "0" => Ok(NixCacheInfoDatum::WantMassQuery(false)),
+                "1" => Ok(NixCacheInfoDatum::WantMassQuery(true)),
+                _ => Err(ParseErr::InvalidBool(remainder)),
+            },
+            "Priority" => Ok(NixCacheInfoDatum::Priority(
+                remainder
+                    .parse::<i32>()
+                    .map_err(|e| ParseErr::InvalidInt(remainder, e))?,
+            )),
+            unknown_key => Err(ParseErr::LineUnknownKey(unknown_key)),
+        }
+    }
+
+    pub fn parse_string(s: &str) -> Result<NixCacheInfo, ParseErr<'_>> {
+        let mut store_dir = None;
+        let mut want_mass_query = None;
+        let mut priority = None;
+
+        for line in s.lines() {
+            if line.is_empty() {
+                continue;
+            }
+
+            match Self::parse_line(line) {
+                Ok(NixCacheInfoDatum::StoreDir(v)) => store_dir = Some(PathBuf::from(v)),
+                Ok(NixCacheInfoDatum::WantMassQuery(v)) => want_mass_query = Some(v),
+                Ok(NixCacheInfoDatum::Priority(v)) => priority = Some(v),
+                // Tolerate unknown keys, the same line discipline NarInfo
+                // uses, so a cache adding a new field doesn't break parsing.
+                Err(ParseErr::LineUnknownKey(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(NixCacheInfo {
+            store_dir: store_dir.unwrap_or_else(|| PathBuf::from("/nix/store")),
+            want_mass_query: want_mass_query.unwrap_or(false),
+            priority: priority.unwrap_or(40),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_store_dir() {
+        assert_eq!(
+            NixCacheInfo::parse_line("StoreDir: /nix/store"),
+            Ok(NixCacheInfoDatum::StoreDir("/nix/store"))
+        );
+    }
+
+    #[test]
+    fn parse_line_want_mass_query_invalid() {
+        assert_eq!(
+            NixCacheInfo::parse_line("WantMassQuery: yes"),
+            Err(ParseErr::InvalidBool("yes"))
+        );
+    }
+
+    #[test]
+    fn parse_string_full() {
+        let info = NixCacheInfo::parse_string(
+            "StoreDir: /nix/store\nWantMassQuery: 1\nPriority: 30",
+        )
+        .expect("should parse");
+
+        assert_eq!(
+            info,
+            NixCacheInfo {
+                store_dir: PathBuf::from("/nix/store"),
+                want_mass_query: true,
+                priority: 30,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_string_defaults() {
+        let info = NixCacheInfo::parse_string("StoreDir: /nix/store").expect("should parse");
+
+        assert_eq!(
+            info,
+            NixCacheInfo {
+                store_dir: PathBuf::from("/nix/store"),
+                want_mass_query: false,
+                priority: 40,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_string_tolerates_unknown_keys() {
+        let info = NixCacheInfo::parse_string("StoreDir: /nix/store\nNotARealKey: whatever")
+            .expect("should parse");
+
+        assert_eq!(
+            info,
+            NixCacheInfo {
+                store_dir: PathBuf::from("/nix/store"),
+                want_mass_query: false,
+                priority: 40,
+            }
+        );
+    }
+}
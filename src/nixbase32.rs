@@ -0,0 +1,82 @@
+//! Nix's custom base32 encoding, used for store path hashes and for most
+//! narinfo hash fields. The alphabet omits `e`, `o`, `u`, and `t` to avoid
+//! spelling offensive words.
+
+const ALPHABET: &[u8; 32] = b"0123456789abcdfghijklmnpqrsvwxyz";
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Nixbase32DecodeError {
+    InvalidChar(char),
+}
+
+/// Decodes a nixbase32 string into its raw bytes, reading characters from
+/// last to first and placing 5 bits of each into the output buffer.
+pub fn decode(input: &str) -> Result<Vec<u8>, Nixbase32DecodeError> {
+    let output_len = input.len() * 5 / 8;
+    let mut output = vec![0u8; output_len];
+
+    for (n, c) in input.chars().rev().enumerate() {
+        let digit = ALPHABET
+            .iter()
+            .position(|&a| a == c as u8)
+            .ok_or(Nixbase32DecodeError::InvalidChar(c))? as u16;
+
+        let b = n * 5;
+        let i = b / 8;
+        let j = b % 8;
+
+        output[i] |= (digit << j) as u8;
+        if i + 1 < output.len() {
+            output[i + 1] |= (digit >> (8 - j)) as u8;
+        }
+    }
+
+    Ok(output)
+}
+
+/// Encodes raw bytes into a nixbase32 string.
+pub fn encode(input: &[u8]) -> String {
+    let len = (input.len() * 8).div_ceil(5);
+    let mut output = vec![0u8; len];
+
+    for n in 0..len {
+        let b = n * 5;
+        let i = b / 8;
+        let j = b % 8;
+
+        let mut c = input[i] >> j;
+        if j > 0 && i + 1 < input.len() {
+            c |= input[i + 1] << (8 - j);
+        }
+
+        output[len - n - 1] = ALPHABET[(c & 0x1f) as usize];
+    }
+
+    String::from_utf8(output).expect("alphabet is ASCII")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_invalid_char() {
+        assert_eq!(decode("e"), Err(Nixbase32DecodeError::InvalidChar('e')));
+    }
+
+    #[test]
+    fn roundtrip_sha256_digest() {
+        let digest = [0x42u8; 32];
+        let encoded = encode(&digest);
+        assert_eq!(encoded.len(), 52);
+        assert_eq!(decode(&encoded).unwrap(), digest);
+    }
+
+    #[test]
+    fn decode_known_store_path_hash() {
+        // The hash part of a real cache.nixos.org store path.
+        let decoded = decode("xmxgxig6zxrixicc7905ssgb4yc3lysa").unwrap();
+        assert_eq!(decoded.len(), 20);
+        assert_eq!(encode(&decoded), "xmxgxig6zxrixicc7905ssgb4yc3lysa");
+    }
+}
@@ -0,0 +1,142 @@
+//! Trusted public keys, in the `name:base64` form used by `nix.conf`'s
+//! `trusted-public-keys` setting, and the machinery to check a `NarInfo`
+//! signature against them.
+
+use data_encoding::BASE64;
+use ed25519_dalek::{Signature, VerifyingKey};
+
+/// A public key used to verify narinfo signatures, e.g.
+/// `cache.nixos.org-1:6NCHdD59X431o0gWypbMrAURkbJ16ZPMQFGspcDShjY=`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct PubKey {
+    pub name: String,
+    key: VerifyingKey,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum PubKeyParseErr {
+    /// There was no `:` separating the name from the base64 key.
+    MissingColon,
+    /// The part after the `:` was not valid base64.
+    InvalidBase64,
+    /// The decoded key was not 32 bytes, or was not a valid point.
+    InvalidKey,
+}
+
+impl PubKey {
+    pub fn parse(s: &str) -> Result<Self, PubKeyParseErr> {
+        let (name, encoded) = s.split_once(':').ok_or(PubKeyParseErr::MissingColon)?;
+
+        let decoded = BASE64
+            .decode(encoded.as_bytes())
+            .map_err(|_| PubKeyParseErr::InvalidBase64)?;
+
+        let bytes: [u8; 32] = decoded
+            .try_into()
+            .map_err(|_| PubKeyParseErr::InvalidKey)?;
+
+        let key = VerifyingKey::from_bytes(&bytes).map_err(|_| PubKeyParseErr::InvalidKey)?;
+
+        Ok(PubKey {
+            name: name.to_string(),
+            key,
+        })
+    }
+}
+
+/// A single `name:base64(ed25519sig)` signature, as found on a `Sig:` line.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ParsedSignature<'a> {
+    pub name: &'a str,
+    signature: Signature,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum SignatureParseErr {
+    MissingColon,
+    InvalidBase64,
+    InvalidSignature,
+}
+
+impl<'a> ParsedSignature<'a> {
+    pub fn parse(s: &'a str) -> Result<Self, SignatureParseErr> {
+        let (name, encoded) = s.split_once(':').ok_or(SignatureParseErr::MissingColon)?;
+
+        let decoded = BASE64
+            .decode(encoded.as_bytes())
+            .map_err(|_| SignatureParseErr::InvalidBase64)?;
+
+        let bytes: [u8; 64] = decoded
+            .try_into()
+            .map_err(|_| SignatureParseErr::InvalidSignature)?;
+
+        Ok(ParsedSignature {
+            name,
+            signature: Signature::from_bytes(&bytes),
+        })
+    }
+
+    /// Checks this signature against `fingerprint` using `key`, if `key`'s
+    /// name matches this signature's name.
+    pub fn verify(&self, fingerprint: &str, key: &PubKey) -> bool {
+        self.name == key.name
+            && key
+                .key
+                .verify_strict(fingerprint.as_bytes(), &self.signature)
+                .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pubkey_parse_missing_colon() {
+        assert_eq!(PubKey::parse("nocolonhere"), Err(PubKeyParseErr::MissingColon));
+    }
+
+    #[test]
+    fn pubkey_parse_invalid_base64() {
+        assert_eq!(
+            PubKey::parse("cache.nixos.org-1:not valid base64!!"),
+            Err(PubKeyParseErr::InvalidBase64)
+        );
+    }
+
+    #[test]
+    fn pubkey_parse_valid() {
+        let pk = PubKey::parse(
+            "cache.nixos.org-1:6NCHdD59X431o0gWypbMrAURkbJ16ZPMQFGspcDShjY=",
+        )
+        .expect("should parse");
+        assert_eq!(pk.name, "cache.nixos.org-1");
+    }
+
+    #[test]
+    fn signature_parse_missing_colon() {
+        assert_eq!(
+            ParsedSignature::parse("nocolonhere"),
+            Err(SignatureParseErr::MissingColon)
+        );
+    }
+
+    #[test]
+    fn verify_accepts_matching_signature() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let pubkey = PubKey::parse(&format!(
+            "test-key-1:{}",
+            BASE64.encode(signing_key.verifying_key().as_bytes())
+        ))
+        .expect("should parse");
+
+        let fingerprint = "1;/nix/store/xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23;sha256:1h7ymc5i5rwfb5rbdyqvfsd0yzsp0rivxcnxn1yh3bp7p963x4nz;456;";
+        let signature = signing_key.sign(fingerprint.as_bytes());
+        let sig_line = format!("test-key-1:{}", BASE64.encode(&signature.to_bytes()));
+
+        let parsed = ParsedSignature::parse(&sig_line).expect("should parse");
+        assert!(parsed.verify(fingerprint, &pubkey));
+    }
+}
@@ -0,0 +1,129 @@
+//! Structured parsing of a Nix store path's `<digest>-<name>` component,
+//! the part after `/nix/store/`. Shared by [`crate::NarInfoId`] and
+//! [`crate::DerivationId`].
+
+use crate::nixbase32;
+
+/// nixbase32 encodes the 20-byte store path hash in exactly 32 characters.
+pub(crate) const DIGEST_LEN: usize = 32;
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum StorePathParseErr<'a> {
+    /// No longer than a digest, leaving no room for a `-` and a name.
+    TooShort(&'a str),
+    /// No `-` separating the digest from the name.
+    MissingSeparator(&'a str),
+    /// The digest wasn't valid nixbase32.
+    InvalidDigest(&'a str),
+    /// The digest decoded to something other than 20 bytes.
+    InvalidDigestLength(&'a str, usize),
+    /// There were no characters after the `-`.
+    EmptyName(&'a str),
+    /// The name contained a character outside `A-Za-z0-9+-._?=`.
+    InvalidNameChar(&'a str, char),
+    /// A `DerivationId` didn't end in `.drv`.
+    MissingDrvSuffix(&'a str),
+}
+
+fn is_valid_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "+-._?=".contains(c)
+}
+
+/// Splits `s` into its digest bytes and name, validating both.
+pub(crate) fn parse_component(s: &str) -> Result<(Vec<u8>, &str), StorePathParseErr<'_>> {
+    if s.len() <= DIGEST_LEN {
+        return Err(StorePathParseErr::TooShort(s));
+    }
+
+    // The digest is nixbase32, which is always ASCII, so a multi-byte
+    // character can never legitimately straddle this offset.
+    if !s.is_char_boundary(DIGEST_LEN) {
+        return Err(StorePathParseErr::InvalidDigest(s));
+    }
+
+    let (digest_str, rest) = s.split_at(DIGEST_LEN);
+    let name = rest
+        .strip_prefix('-')
+        .ok_or(StorePathParseErr::MissingSeparator(s))?;
+
+    let digest =
+        nixbase32::decode(digest_str).map_err(|_| StorePathParseErr::InvalidDigest(s))?;
+    if digest.len() != 20 {
+        return Err(StorePathParseErr::InvalidDigestLength(s, digest.len()));
+    }
+
+    if name.is_empty() {
+        return Err(StorePathParseErr::EmptyName(s));
+    }
+
+    if let Some(c) = name.chars().find(|&c| !is_valid_name_char(c)) {
+        return Err(StorePathParseErr::InvalidNameChar(s, c));
+    }
+
+    Ok((digest, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_component_valid() {
+        let (digest, name) =
+            parse_component("xmxgxig6zxrixicc7905ssgb4yc3lysa-bash-interactive-4.4-p23")
+                .expect("should parse");
+        assert_eq!(digest.len(), 20);
+        assert_eq!(name, "bash-interactive-4.4-p23");
+    }
+
+    #[test]
+    fn parse_component_too_short() {
+        assert_eq!(
+            parse_component("tooshort"),
+            Err(StorePathParseErr::TooShort("tooshort"))
+        );
+    }
+
+    #[test]
+    fn parse_component_missing_separator() {
+        let s = "xmxgxig6zxrixicc7905ssgb4yc3lysaXbash";
+        assert_eq!(
+            parse_component(s),
+            Err(StorePathParseErr::MissingSeparator(s))
+        );
+    }
+
+    #[test]
+    fn parse_component_empty_name() {
+        let s = "xmxgxig6zxrixicc7905ssgb4yc3lysa-";
+        assert_eq!(parse_component(s), Err(StorePathParseErr::EmptyName(s)));
+    }
+
+    #[test]
+    fn parse_component_invalid_digest_char() {
+        // 'e' is not in the nixbase32 alphabet.
+        let s = "eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee-name";
+        assert_eq!(
+            parse_component(s),
+            Err(StorePathParseErr::InvalidDigest(s))
+        );
+    }
+
+    #[test]
+    fn parse_component_multibyte_char_straddling_digest_boundary() {
+        let s = "あ".repeat(11) + "-x";
+        assert_eq!(
+            parse_component(&s),
+            Err(StorePathParseErr::InvalidDigest(&s))
+        );
+    }
+
+    #[test]
+    fn parse_component_invalid_name_char() {
+        let s = "xmxgxig6zxrixicc7905ssgb4yc3lysa-has space";
+        assert_eq!(
+            parse_component(s),
+            Err(StorePathParseErr::InvalidNameChar(s, ' '))
+        );
+    }
+}